@@ -0,0 +1,141 @@
+use crate::stack::InspectorStack;
+use alloy_primitives::B256;
+use revm::{
+    inspector_handle_register,
+    primitives::{EVMError, Env},
+    Database, DatabaseCommit, Evm,
+};
+
+/// One transaction to replay as part of [`trace_block`], pairing its fully built [`Env`] with the
+/// hash used to match [`Hook::Transaction`](crate::stack::Hook::Transaction).
+pub struct ReplayTx {
+    /// The transaction hash, matched against [`Hook::Transaction`](crate::stack::Hook::Transaction).
+    pub tx_hash: B256,
+    /// The environment (block + tx) to execute this transaction under.
+    pub env: Env,
+}
+
+/// Replays a block's transactions in order, activating `stack` only for the transactions that
+/// [`InspectorStack::should_inspect`] selects, and returns one slot per transaction: `Some` with
+/// whatever `extract` produces from the stack for transactions that were inspected, `None`
+/// otherwise.
+///
+/// Every transaction is executed and committed to `db` regardless of whether it is inspected, so
+/// state stays consistent for the rest of the block. Between inspected transactions the stack's
+/// stateful tracers are reset via [`InspectorStack::clear`] so they don't carry over state from
+/// one transaction to the next.
+///
+/// With [`Hook::Transaction`](crate::stack::Hook::Transaction) this produces a single `Some`
+/// entry; with [`Hook::All`](crate::stack::Hook::All) every entry is `Some`; with
+/// [`Hook::Block`](crate::stack::Hook::Block) the whole block is gated on the block number
+/// encoded in each `env`; with [`Hook::None`](crate::stack::Hook::None) every entry is `None`.
+///
+/// Bails out on the first transaction that fails to execute, returning that transaction's error;
+/// none of the transactions after it are replayed, and `results` for everything up to and
+/// including the failing transaction are discarded since `db` may already reflect a partial
+/// commit for it.
+pub fn trace_block<DB, F, T>(
+    stack: &mut InspectorStack<DB>,
+    db: &mut DB,
+    txs: &[ReplayTx],
+    mut extract: F,
+) -> Result<Vec<Option<T>>, EVMError<<DB as Database>::Error>>
+where
+    DB: Database + DatabaseCommit,
+    F: FnMut(&mut InspectorStack<DB>) -> T,
+{
+    let mut results = Vec::with_capacity(txs.len());
+
+    for tx in txs {
+        if !stack.should_inspect(&tx.env, tx.tx_hash) {
+            let mut evm = Evm::builder()
+                .with_db(&mut *db)
+                .with_env(Box::new(tx.env.clone()))
+                .build();
+            evm.transact_commit()?;
+            results.push(None);
+            continue;
+        }
+
+        stack.clear();
+
+        let mut evm = Evm::builder()
+            .with_db(&mut *db)
+            .with_env(Box::new(tx.env.clone()))
+            .with_external_context(&mut *stack)
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact_commit()?;
+        drop(evm);
+
+        results.push(Some(extract(stack)));
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::{Hook, InspectorStack, InspectorStackConfig};
+    use alloy_primitives::{Address, U256};
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::TransactTo,
+    };
+
+    fn replay_tx(block_number: u64) -> ReplayTx {
+        let mut env = Env::default();
+        env.block.number = U256::from(block_number);
+        env.tx.caller = Address::with_last_byte(1);
+        env.tx.transact_to = TransactTo::Call(Address::with_last_byte(2));
+        env.tx.gas_limit = 100_000;
+        env.tx.gas_price = U256::from(1);
+        ReplayTx {
+            tx_hash: B256::with_last_byte(block_number as u8),
+            env,
+        }
+    }
+
+    #[test]
+    fn hook_none_inspects_no_transaction() {
+        let mut stack = InspectorStack::new(InspectorStackConfig {
+            hook: Hook::None,
+            ..Default::default()
+        });
+        let mut db = CacheDB::new(EmptyDB::default());
+        let txs = vec![replay_tx(1), replay_tx(2)];
+
+        let results = trace_block(&mut stack, &mut db, &txs, |_| ()).unwrap();
+
+        assert_eq!(results, vec![None, None]);
+    }
+
+    #[test]
+    fn hook_all_inspects_every_transaction() {
+        let mut stack = InspectorStack::new(InspectorStackConfig {
+            hook: Hook::All,
+            ..Default::default()
+        });
+        let mut db = CacheDB::new(EmptyDB::default());
+        let txs = vec![replay_tx(1), replay_tx(2)];
+
+        let results = trace_block(&mut stack, &mut db, &txs, |_| ()).unwrap();
+
+        assert_eq!(results, vec![Some(()), Some(())]);
+    }
+
+    #[test]
+    fn hook_block_only_inspects_matching_block_number() {
+        let mut stack = InspectorStack::new(InspectorStackConfig {
+            hook: Hook::Block(2),
+            ..Default::default()
+        });
+        let mut db = CacheDB::new(EmptyDB::default());
+        let txs = vec![replay_tx(1), replay_tx(2)];
+
+        let results = trace_block(&mut stack, &mut db, &txs, |_| ()).unwrap();
+
+        assert_eq!(results, vec![None, Some(())]);
+    }
+}
@@ -0,0 +1,330 @@
+use alloy_primitives::{Address, Bytes, Log, U256};
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, CreateScheme},
+    Database, EvmContext, Inspector,
+};
+
+/// The kind of call/create that produced a [`CallTraceNode`], matching the `"type"` field geth's
+/// `callTracer` puts on every frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallKind {
+    /// A plain `CALL`.
+    Call,
+    /// A `STATICCALL`.
+    StaticCall,
+    /// A `CALLCODE`.
+    CallCode,
+    /// A `DELEGATECALL`.
+    DelegateCall,
+    /// A `CREATE`.
+    Create,
+    /// A `CREATE2`.
+    Create2,
+}
+
+impl CallKind {
+    /// Returns the geth-style uppercase opcode name used in the JSON trace.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Call => "CALL",
+            Self::StaticCall => "STATICCALL",
+            Self::CallCode => "CALLCODE",
+            Self::DelegateCall => "DELEGATECALL",
+            Self::Create => "CREATE",
+            Self::Create2 => "CREATE2",
+        }
+    }
+}
+
+impl From<CallScheme> for CallKind {
+    fn from(scheme: CallScheme) -> Self {
+        match scheme {
+            CallScheme::Call => Self::Call,
+            CallScheme::StaticCall => Self::StaticCall,
+            CallScheme::CallCode => Self::CallCode,
+            CallScheme::DelegateCall => Self::DelegateCall,
+        }
+    }
+}
+
+impl From<CreateScheme> for CallKind {
+    fn from(scheme: CreateScheme) -> Self {
+        match scheme {
+            CreateScheme::Create => Self::Create,
+            CreateScheme::Create2 { .. } => Self::Create2,
+        }
+    }
+}
+
+/// A single frame of the call graph recorded by [`CallTracer`].
+///
+/// Frames are stored in a flat [`CallTraceArena`] and reference each other by index rather than
+/// owning their children, so that sibling calls keep insertion order and the arena can be built
+/// with a single pass over the trace.
+#[derive(Clone, Debug)]
+pub struct CallTraceNode {
+    /// Index of the parent frame in the arena, or `None` for the outermost call.
+    pub parent: Option<usize>,
+    /// Indices of the child frames, in call order.
+    pub children: Vec<usize>,
+    /// The kind of call/create that opened this frame.
+    pub kind: CallKind,
+    /// The account that initiated this frame.
+    pub from: Address,
+    /// The callee, or `None` for a create before the deployed address is known.
+    pub to: Option<Address>,
+    /// The value transferred with the call/create.
+    pub value: U256,
+    /// The calldata (for calls) or init code (for creates).
+    pub input: Bytes,
+    /// The return data (for calls) or deployed bytecode (for successful creates).
+    pub output: Bytes,
+    /// Gas supplied to the frame.
+    pub gas_limit: u64,
+    /// Gas consumed by the frame, filled in when the frame returns.
+    pub gas_used: u64,
+    /// Whether the frame completed successfully.
+    pub success: bool,
+    /// The `Debug` name of the frame's `InstructionResult` (e.g. `Revert`, `OutOfGas`), if the
+    /// frame did not succeed. The revert reason is not decoded from the return data.
+    pub error: Option<String>,
+    /// Logs emitted directly in this frame (not in sub-calls), in emission order.
+    pub logs: Vec<Log>,
+}
+
+/// A flat arena of [`CallTraceNode`]s describing the call graph of a single transaction.
+#[derive(Clone, Debug, Default)]
+pub struct CallTraceArena {
+    /// All recorded frames, in the order their calls were opened.
+    pub nodes: Vec<CallTraceNode>,
+}
+
+impl CallTraceArena {
+    fn push_node(&mut self, parent: Option<usize>, node: CallTraceNode) -> usize {
+        let idx = self.nodes.len();
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(idx);
+        }
+        self.nodes.push(node);
+        idx
+    }
+
+    /// Serializes the frame at `idx` and its children into geth's nested `callTracer` JSON shape.
+    pub fn to_geth_json(&self, idx: usize) -> serde_json::Value {
+        let node = &self.nodes[idx];
+        let mut call = serde_json::json!({
+            "type": node.kind.as_str(),
+            "from": node.from,
+            "value": node.value,
+            "gas": format!("0x{:x}", node.gas_limit),
+            "gasUsed": format!("0x{:x}", node.gas_used),
+            "input": node.input,
+            "output": node.output,
+        });
+
+        if let Some(to) = node.to {
+            call["to"] = serde_json::json!(to);
+        }
+        if let Some(error) = &node.error {
+            call["error"] = serde_json::json!(error);
+        }
+        if !node.children.is_empty() {
+            call["calls"] = serde_json::Value::Array(
+                node.children
+                    .iter()
+                    .map(|&child| self.to_geth_json(child))
+                    .collect(),
+            );
+        }
+
+        call
+    }
+
+    /// Serializes the outermost call (index `0`) as the top-level `debug_traceTransaction` result.
+    ///
+    /// Returns `null` if no frame was ever recorded.
+    pub fn to_geth_json_root(&self) -> serde_json::Value {
+        if self.nodes.is_empty() {
+            return serde_json::Value::Null;
+        }
+        self.to_geth_json(0)
+    }
+}
+
+/// Records the full call graph of a transaction into a [`CallTraceArena`], the way geth's
+/// `callTracer` does for `debug_traceTransaction`.
+#[derive(Clone, Debug, Default)]
+pub struct CallTracer {
+    /// The call graph recorded so far.
+    pub arena: CallTraceArena,
+    /// Stack of currently open frame indices, innermost (currently executing) last.
+    current_idx: Vec<usize>,
+}
+
+impl CallTracer {
+    /// Consumes the tracer, returning the recorded call graph.
+    pub fn into_arena(self) -> CallTraceArena {
+        self.arena
+    }
+
+    /// Resets the tracer to its freshly constructed state so it can be reused for the next
+    /// transaction.
+    pub fn clear(&mut self) {
+        self.arena.nodes.clear();
+        self.current_idx.clear();
+    }
+
+    fn active_idx(&self) -> Option<usize> {
+        self.current_idx.last().copied()
+    }
+}
+
+impl<DB> Inspector<DB> for CallTracer
+where
+    DB: Database,
+{
+    fn log(&mut self, _context: &mut EvmContext<DB>, log: &Log) {
+        if let Some(idx) = self.active_idx() {
+            self.arena.nodes[idx].logs.push(log.clone());
+        }
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let node = CallTraceNode {
+            parent: self.active_idx(),
+            children: Vec::new(),
+            kind: inputs.scheme.into(),
+            from: inputs.caller,
+            to: Some(inputs.target_address),
+            value: inputs.value.get(),
+            input: inputs.input.clone(),
+            output: Bytes::new(),
+            gas_limit: inputs.gas_limit,
+            gas_used: 0,
+            success: true,
+            error: None,
+            logs: Vec::new(),
+        };
+        let idx = self.arena.push_node(self.active_idx(), node);
+        self.current_idx.push(idx);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        if let Some(idx) = self.current_idx.pop() {
+            let node = &mut self.arena.nodes[idx];
+            node.gas_used = node.gas_limit.saturating_sub(outcome.gas().remaining());
+            node.output = outcome.output().clone();
+            node.success = !outcome.result.result.is_revert() && !outcome.result.result.is_error();
+            if !node.success {
+                node.error = Some(format!("{:?}", outcome.result.result));
+            }
+        }
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let node = CallTraceNode {
+            parent: self.active_idx(),
+            children: Vec::new(),
+            kind: inputs.scheme.into(),
+            from: inputs.caller,
+            to: None,
+            value: inputs.value,
+            input: inputs.init_code.clone(),
+            output: Bytes::new(),
+            gas_limit: inputs.gas_limit,
+            gas_used: 0,
+            success: true,
+            error: None,
+            logs: Vec::new(),
+        };
+        let idx = self.arena.push_node(self.active_idx(), node);
+        self.current_idx.push(idx);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if let Some(idx) = self.current_idx.pop() {
+            let node = &mut self.arena.nodes[idx];
+            node.gas_used = node.gas_limit.saturating_sub(outcome.gas().remaining());
+            node.to = outcome.address;
+            node.success = !outcome.result.result.is_revert() && !outcome.result.result.is_error();
+            if node.success {
+                node.output = outcome.output().clone();
+            } else {
+                node.error = Some(format!("{:?}", outcome.result.result));
+            }
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(parent: Option<usize>) -> CallTraceNode {
+        CallTraceNode {
+            parent,
+            children: Vec::new(),
+            kind: CallKind::Call,
+            from: Address::ZERO,
+            to: Some(Address::ZERO),
+            value: U256::ZERO,
+            input: Bytes::new(),
+            output: Bytes::new(),
+            gas_limit: 0,
+            gas_used: 0,
+            success: true,
+            error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn push_node_links_parent_and_child() {
+        let mut arena = CallTraceArena::default();
+        let root = arena.push_node(None, node(None));
+        let child = arena.push_node(Some(root), node(Some(root)));
+
+        assert_eq!(root, 0);
+        assert_eq!(child, 1);
+        assert_eq!(arena.nodes[root].children, vec![child]);
+        assert_eq!(arena.nodes[child].parent, Some(root));
+    }
+
+    #[test]
+    fn to_geth_json_root_nests_children() {
+        let mut arena = CallTraceArena::default();
+        let root = arena.push_node(None, node(None));
+        arena.push_node(Some(root), node(Some(root)));
+
+        let json = arena.to_geth_json_root();
+        assert_eq!(json["calls"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn to_geth_json_root_is_null_when_empty() {
+        let arena = CallTraceArena::default();
+        assert!(arena.to_geth_json_root().is_null());
+    }
+}
@@ -0,0 +1,388 @@
+use crate::gas::GasInspector;
+use alloy_primitives::{hex, Bytes, U256};
+use revm::{
+    interpreter::{opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use serde::Serialize;
+use std::{fmt, io::Write, time::Instant};
+
+/// A single EIP-3155 `structLog` entry, emitted as one line of JSON per executed opcode.
+///
+/// See <https://eips.ethereum.org/EIPS/eip-3155>.
+#[derive(Clone, Debug, Serialize)]
+pub struct Eip3155Log {
+    /// Program counter.
+    pub pc: u64,
+    /// Numeric opcode.
+    pub op: u8,
+    /// The mnemonic name of `op`.
+    #[serde(rename = "opName")]
+    pub op_name: &'static str,
+    /// Remaining gas, as a `0x`-prefixed hex string.
+    pub gas: String,
+    /// The gas cost of this opcode, as a `0x`-prefixed hex string.
+    #[serde(rename = "gasCost")]
+    pub gas_cost: String,
+    /// Call depth, starting at `1` for the top-level frame.
+    pub depth: u64,
+    /// The full stack, bottom to top.
+    pub stack: Vec<U256>,
+    /// Hex-encoded memory contents, if memory capture was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    /// Hex-encoded return data of the last completed call, if any.
+    #[serde(rename = "returnData", skip_serializing_if = "Option::is_none")]
+    pub return_data: Option<String>,
+    /// Gas refund counter accumulated so far.
+    pub refund: u64,
+}
+
+/// The summary object EIP-3155 emits once at the end of a transaction.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Eip3155Summary {
+    /// Hex-encoded return value of the transaction.
+    pub output: String,
+    /// Total gas used by the transaction, as a `0x`-prefixed hex string.
+    #[serde(rename = "gasUsed")]
+    pub gas_used: String,
+    /// Wall-clock execution time, in nanoseconds.
+    pub time: u64,
+    /// The error message, if the transaction failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// An EIP-3155 conformant struct-log tracer with a pluggable output sink.
+///
+/// Install a [`Write`] sink with [`Eip3155Tracer::with_writer`] to stream `structLog` lines to
+/// stdout, a file, or an in-memory buffer as they are produced. Call [`Eip3155Tracer::clear`]
+/// between transactions to reuse a single tracer across a whole block without carrying over
+/// stale state.
+pub struct Eip3155Tracer {
+    record_memory: bool,
+    writer: Option<Box<dyn Write + Send>>,
+
+    depth: u64,
+    pc: u64,
+    op: u8,
+    op_name: &'static str,
+    stack: Vec<U256>,
+    memory: Option<Bytes>,
+    return_data: Bytes,
+    refund: u64,
+    gas: GasInspector,
+    gas_before_op: u64,
+    /// `structLog` lines for in-flight `CALL`/`CREATE` opcodes, innermost last, held back until
+    /// the matching `call_end`/`create_end` has revised their `gasCost` to account for the
+    /// subcall's actual consumption.
+    pending_calls: Vec<Eip3155Log>,
+    /// One entry per in-flight `call`/`create`, innermost last: whether it has reached
+    /// `initialize_interp` and so actually entered a frame. A call to a precompile, an EOA, or an
+    /// empty-code account, and a `CREATE` that fails its pre-checks, reach `call_end`/`create_end`
+    /// without ever entering a frame, and must not decrement `depth`.
+    frame_entered: Vec<bool>,
+    started_at: Option<Instant>,
+}
+
+impl Default for Eip3155Tracer {
+    fn default() -> Self {
+        Self {
+            record_memory: false,
+            writer: None,
+            depth: 0,
+            pc: 0,
+            op: 0,
+            op_name: "",
+            stack: Vec::new(),
+            memory: None,
+            return_data: Bytes::new(),
+            refund: 0,
+            gas: GasInspector::new(),
+            gas_before_op: 0,
+            pending_calls: Vec::new(),
+            frame_entered: Vec::new(),
+            started_at: None,
+        }
+    }
+}
+
+impl fmt::Debug for Eip3155Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Eip3155Tracer")
+            .field("record_memory", &self.record_memory)
+            .field("writer", &self.writer.is_some())
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
+impl Eip3155Tracer {
+    /// Creates a new tracer with no output sink and memory capture disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Installs the sink that `structLog` lines and the final summary are written to.
+    pub fn with_writer(mut self, writer: Box<dyn Write + Send>) -> Self {
+        self.writer = Some(writer);
+        self
+    }
+
+    /// Enables or disables capturing memory contents on each `structLog` line.
+    pub fn with_memory(mut self, record_memory: bool) -> Self {
+        self.record_memory = record_memory;
+        self
+    }
+
+    /// Resets the tracer to its freshly constructed state, clearing the captured stack, pc, op,
+    /// refund counter, and embedded [`GasInspector`], so the same tracer can be reused across
+    /// every transaction in a block.
+    ///
+    /// The output sink and the memory-capture setting are preserved.
+    pub fn clear(&mut self) {
+        self.depth = 0;
+        self.pc = 0;
+        self.op = 0;
+        self.op_name = "";
+        self.stack.clear();
+        self.memory = None;
+        self.return_data = Bytes::new();
+        self.refund = 0;
+        self.gas.clear();
+        self.gas_before_op = 0;
+        self.pending_calls.clear();
+        self.frame_entered.clear();
+        self.started_at = None;
+    }
+
+    fn write_line(&mut self, line: &impl Serialize) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        if let Ok(json) = serde_json::to_string(line) {
+            let _ = writeln!(writer, "{json}");
+        }
+    }
+
+    /// Builds the `structLog` line for the opcode that just finished executing.
+    fn build_step(&self) -> Eip3155Log {
+        Eip3155Log {
+            pc: self.pc,
+            op: self.op,
+            op_name: self.op_name,
+            gas: format!("0x{:x}", self.gas_before_op),
+            gas_cost: format!("0x{:x}", self.gas.last_gas_cost()),
+            depth: self.depth,
+            stack: self.stack.clone(),
+            memory: self
+                .memory
+                .as_ref()
+                .map(|mem| format!("0x{}", hex::encode(mem))),
+            return_data: (!self.return_data.is_empty())
+                .then(|| format!("0x{}", hex::encode(&self.return_data))),
+            refund: self.refund,
+        }
+    }
+
+    /// Revises and emits the `structLog` line of the `CALL`/`CREATE` opcode that triggered the
+    /// frame which just returned, now that its true gas cost is known.
+    fn flush_pending_call(&mut self) {
+        if let Some(mut log) = self.pending_calls.pop() {
+            log.gas_cost = format!("0x{:x}", self.gas.last_gas_cost());
+            self.write_line(&log);
+        }
+    }
+
+    /// Emits the end-of-transaction summary object, and resets `time` tracking.
+    ///
+    /// Call this after the transaction has finished executing.
+    pub fn write_summary(&mut self, output: &Bytes, gas_used: u64, error: Option<String>) {
+        let time = self
+            .started_at
+            .take()
+            .map(|start| start.elapsed().as_nanos() as u64)
+            .unwrap_or_default();
+        let summary = Eip3155Summary {
+            output: format!("0x{}", hex::encode(output)),
+            gas_used: format!("0x{gas_used:x}"),
+            time,
+            error,
+        };
+        self.write_line(&summary);
+    }
+}
+
+impl<DB> Inspector<DB> for Eip3155Tracer
+where
+    DB: Database,
+{
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.frame_entered.push(false);
+        self.gas.call(context, inputs)
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.frame_entered.push(false);
+        self.gas.create(context, inputs)
+    }
+
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.started_at.get_or_insert_with(Instant::now);
+        self.depth += 1;
+        if let Some(entered) = self.frame_entered.last_mut() {
+            *entered = true;
+        }
+        self.gas.initialize_interp(interp, context);
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.pc = interp.program_counter() as u64;
+        self.op = interp.current_opcode();
+        self.op_name = interp.opcode_name();
+        self.stack = interp.stack().data().clone();
+        self.return_data = interp.return_data_buffer.clone();
+        if self.record_memory {
+            self.memory = Some(Bytes::copy_from_slice(
+                interp.shared_memory.context_memory(),
+            ));
+        }
+        self.gas.step(interp, context);
+        // `gas.step` records the remaining gas as of just before this opcode executes; snapshot
+        // it now since `step_end` will overwrite it with the post-opcode value.
+        self.gas_before_op = self.gas.gas_remaining();
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.refund = interp.gas().refunded() as u64;
+        self.gas.step_end(interp, context);
+        let log = self.build_step();
+        if matches!(
+            self.op,
+            opcode::CALL
+                | opcode::CALLCODE
+                | opcode::DELEGATECALL
+                | opcode::STATICCALL
+                | opcode::CREATE
+                | opcode::CREATE2
+        ) {
+            // The subcall this opcode triggers hasn't run yet, so `gas_cost` above is only
+            // provisional; hold the line back until `call_end`/`create_end` can revise it with
+            // the gas the subcall actually consumed.
+            self.pending_calls.push(log);
+        } else {
+            self.write_line(&log);
+        }
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        if self.frame_entered.pop().unwrap_or(false) {
+            self.depth = self.depth.saturating_sub(1);
+        }
+        let outcome = self.gas.call_end(context, inputs, outcome);
+        self.flush_pending_call();
+        outcome
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if self.frame_entered.pop().unwrap_or(false) {
+            self.depth = self.depth.saturating_sub(1);
+        }
+        let outcome = self.gas.create_end(context, inputs, outcome);
+        self.flush_pending_call();
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        db::EmptyDB,
+        interpreter::{CallValue, Gas, InstructionResult, InterpreterResult},
+        primitives::Address,
+    };
+
+    fn call_inputs(gas_limit: u64) -> CallInputs {
+        CallInputs {
+            input: Bytes::new(),
+            return_memory_offset: 0..0,
+            gas_limit,
+            bytecode_address: Address::ZERO,
+            target_address: Address::ZERO,
+            caller: Address::ZERO,
+            value: CallValue::Transfer(U256::ZERO),
+            scheme: revm::interpreter::CallScheme::Call,
+            is_static: false,
+            is_eof: false,
+        }
+    }
+
+    fn call_outcome(gas_remaining: u64) -> CallOutcome {
+        CallOutcome::new(
+            InterpreterResult {
+                result: InstructionResult::Stop,
+                output: Bytes::new(),
+                gas: Gas::new(gas_remaining),
+            },
+            0..0,
+        )
+    }
+
+    // A call to a precompile, an EOA, or an empty-code account never reaches
+    // `initialize_interp`, so `depth` must not be decremented for it in `call_end`.
+    #[test]
+    fn call_without_entered_frame_does_not_decrement_depth() {
+        let mut tracer = Eip3155Tracer::new();
+        tracer.depth = 1;
+        let mut context = EvmContext::new(EmptyDB::default());
+        let mut inputs = call_inputs(100);
+
+        assert!(tracer.call(&mut context, &mut inputs).is_none());
+        tracer.call_end(&mut context, &inputs, call_outcome(90));
+
+        assert_eq!(tracer.depth, 1);
+        assert!(tracer.frame_entered.is_empty());
+    }
+
+    // A call that does enter a frame has `initialize_interp` flip its `frame_entered` marker and
+    // increment `depth`; `call_end` must decrement it again exactly once.
+    #[test]
+    fn call_with_entered_frame_decrements_depth() {
+        let mut tracer = Eip3155Tracer::new();
+        tracer.depth = 1;
+        let mut context = EvmContext::new(EmptyDB::default());
+        let mut inputs = call_inputs(100);
+
+        assert!(tracer.call(&mut context, &mut inputs).is_none());
+        assert_eq!(tracer.frame_entered, vec![false]);
+
+        // Simulate what `initialize_interp` does for a call that actually enters a frame,
+        // without needing a real `Interpreter` to drive it.
+        *tracer.frame_entered.last_mut().unwrap() = true;
+        tracer.depth += 1;
+
+        tracer.call_end(&mut context, &inputs, call_outcome(90));
+
+        assert_eq!(tracer.depth, 1);
+        assert!(tracer.frame_entered.is_empty());
+    }
+}
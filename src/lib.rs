@@ -0,0 +1,15 @@
+//! Composable [`revm::Inspector`] implementations for tracing EVM execution.
+
+pub mod block;
+pub mod call_tracer;
+pub mod eip3155;
+pub mod gas;
+pub mod prestate;
+pub mod stack;
+
+pub use block::{trace_block, ReplayTx};
+pub use call_tracer::{CallKind, CallTraceArena, CallTraceNode, CallTracer};
+pub use eip3155::{Eip3155Log, Eip3155Summary, Eip3155Tracer};
+pub use gas::GasInspector;
+pub use prestate::{AccountState, PrestateMode, PrestateTracer};
+pub use stack::{Hook, InspectorStack, InspectorStackConfig};
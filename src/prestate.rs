@@ -0,0 +1,427 @@
+use alloy_primitives::{Address, Bytes, B256, U256};
+use revm::{
+    interpreter::{
+        opcode, CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, Interpreter,
+    },
+    Database, EvmContext, Inspector,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Which of geth's `prestateTracer` output modes a [`PrestateTracer`] produces.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrestateMode {
+    /// Emit only the pre-execution state of every touched account ("prestate" mode).
+    #[default]
+    Prestate,
+    /// Emit `pre`/`post` maps containing only the fields that actually changed ("diff" mode).
+    Diff,
+}
+
+/// The recorded balance, nonce, code, and touched storage slots of a single account.
+#[derive(Clone, Debug, Default)]
+pub struct AccountState {
+    /// The account's balance, if it was read.
+    pub balance: Option<U256>,
+    /// The account's nonce, if it was read.
+    pub nonce: Option<u64>,
+    /// The account's code, if it was read.
+    pub code: Option<Bytes>,
+    /// Storage slots read or written, keyed by slot.
+    pub storage: BTreeMap<B256, B256>,
+}
+
+impl AccountState {
+    /// Serializes this account's state into geth's nested `prestateTracer` account JSON shape.
+    pub fn to_geth_json(&self) -> serde_json::Value {
+        let mut account = serde_json::json!({});
+        if let Some(balance) = self.balance {
+            account["balance"] = serde_json::json!(format!("0x{balance:x}"));
+        }
+        if let Some(nonce) = self.nonce {
+            account["nonce"] = serde_json::json!(nonce);
+        }
+        if let Some(code) = &self.code {
+            account["code"] =
+                serde_json::json!(format!("0x{}", alloy_primitives::hex::encode(code)));
+        }
+        if !self.storage.is_empty() {
+            account["storage"] = serde_json::Value::Object(
+                self.storage
+                    .iter()
+                    .map(|(slot, value)| {
+                        (
+                            format!("{slot:#x}"),
+                            serde_json::json!(format!("{value:#x}")),
+                        )
+                    })
+                    .collect(),
+            );
+        }
+        account
+    }
+}
+
+/// Computes the `pre`/`post` delta for a single account in [`PrestateMode::Diff`] mode.
+///
+/// Returns `None` if the account was never written (only read, e.g. by a `STATICCALL` or a
+/// `BALANCE`/`SLOAD` that didn't lead to a state change), or if nothing in it differs between
+/// `pre` and `post`, since such accounts belong in neither map. Otherwise returns a pair of [`AccountState`]s containing only the balance/nonce/code
+/// fields and storage slots that actually differ between `pre` and `post`.
+fn diff_account(
+    pre: Option<&AccountState>,
+    post: Option<&AccountState>,
+) -> Option<(AccountState, AccountState)> {
+    let post = post?;
+    let empty = AccountState::default();
+    let pre = pre.unwrap_or(&empty);
+
+    let mut pre_diff = AccountState::default();
+    let mut post_diff = AccountState::default();
+
+    if pre.balance != post.balance {
+        pre_diff.balance = pre.balance;
+        post_diff.balance = post.balance;
+    }
+    if pre.nonce != post.nonce {
+        pre_diff.nonce = pre.nonce;
+        post_diff.nonce = post.nonce;
+    }
+    if pre.code != post.code {
+        pre_diff.code = pre.code.clone();
+        post_diff.code = post.code.clone();
+    }
+    for (slot, value) in &post.storage {
+        if pre.storage.get(slot) != Some(value) {
+            pre_diff
+                .storage
+                .insert(*slot, pre.storage.get(slot).copied().unwrap_or_default());
+            post_diff.storage.insert(*slot, *value);
+        }
+    }
+
+    let changed = pre.balance != post.balance
+        || pre.nonce != post.nonce
+        || pre.code != post.code
+        || !post_diff.storage.is_empty();
+    changed.then_some((pre_diff, post_diff))
+}
+
+/// Records every account and storage slot touched by a transaction, the way geth's
+/// `prestateTracer` does for `debug_traceTransaction`.
+///
+/// Pre-state for an address or slot is resolved lazily from the [`EvmContext`]'s journaled state
+/// the first time it is seen, so later writes in the same transaction don't overwrite the
+/// recorded original value. In [`PrestateMode::Diff`] mode, a second snapshot is taken once the
+/// write that caused it has actually executed (at `step_end` for an `SSTORE`, at `call_end`/
+/// `create_end` for balance changes from a value transfer), so the `post` map reflects the true
+/// post-execution state rather than the pre-write value.
+#[derive(Clone, Debug)]
+pub struct PrestateTracer {
+    mode: PrestateMode,
+    pre: BTreeMap<Address, AccountState>,
+    post: BTreeMap<Address, AccountState>,
+    /// An in-flight `SSTORE` seen in `step`, snapshotted into `post` once `step_end` confirms it
+    /// has executed.
+    pending_sstore: Option<(Address, U256)>,
+}
+
+impl PrestateTracer {
+    /// Creates a new tracer operating in the given mode.
+    pub fn new(mode: PrestateMode) -> Self {
+        Self {
+            mode,
+            pre: BTreeMap::new(),
+            post: BTreeMap::new(),
+            pending_sstore: None,
+        }
+    }
+
+    /// Resets the tracer to its freshly constructed state, so it can be reused for the next
+    /// transaction.
+    pub fn clear(&mut self) {
+        self.pre.clear();
+        self.post.clear();
+        self.pending_sstore = None;
+    }
+
+    /// Serializes the recorded state into geth's nested `prestateTracer` account map JSON.
+    ///
+    /// In [`PrestateMode::Prestate`] mode this is the flat pre-state map; in
+    /// [`PrestateMode::Diff`] mode it is a `{"pre": ..., "post": ...}` object.
+    pub fn to_geth_json(&self) -> serde_json::Value {
+        match self.mode {
+            PrestateMode::Prestate => serde_json::Value::Object(
+                self.pre
+                    .iter()
+                    .map(|(addr, state)| (format!("{addr:#x}"), state.to_geth_json()))
+                    .collect(),
+            ),
+            PrestateMode::Diff => {
+                let addresses: BTreeSet<Address> =
+                    self.pre.keys().chain(self.post.keys()).copied().collect();
+
+                let mut pre_json = serde_json::Map::new();
+                let mut post_json = serde_json::Map::new();
+                for address in addresses {
+                    if let Some((pre_diff, post_diff)) =
+                        diff_account(self.pre.get(&address), self.post.get(&address))
+                    {
+                        pre_json.insert(format!("{address:#x}"), pre_diff.to_geth_json());
+                        post_json.insert(format!("{address:#x}"), post_diff.to_geth_json());
+                    }
+                }
+                serde_json::json!({
+                    "pre": serde_json::Value::Object(pre_json),
+                    "post": serde_json::Value::Object(post_json),
+                })
+            }
+        }
+    }
+
+    fn pre_entry(&mut self, address: Address) -> &mut AccountState {
+        self.pre.entry(address).or_default()
+    }
+
+    fn post_entry(&mut self, address: Address) -> &mut AccountState {
+        self.post.entry(address).or_default()
+    }
+
+    /// Ensures the pre-state balance/nonce/code for `address` has been captured, resolving it
+    /// from the journaled state on first sight.
+    fn touch_account<DB: Database>(&mut self, context: &mut EvmContext<DB>, address: Address) {
+        if self.pre.contains_key(&address) {
+            return;
+        }
+
+        let state = self.pre_entry(address);
+        if let Ok((account, _)) = context
+            .journaled_state
+            .load_account(address, &mut context.db)
+        {
+            state.balance = Some(account.info.balance);
+            state.nonce = Some(account.info.nonce);
+            state.code = account.info.code.as_ref().map(|code| code.original_bytes());
+        }
+    }
+
+    /// Ensures the pre-state value of `slot` on `address` has been captured.
+    fn touch_storage<DB: Database>(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        address: Address,
+        slot: U256,
+    ) {
+        self.touch_account(context, address);
+
+        let key = B256::from(slot.to_be_bytes());
+        if self.pre_entry(address).storage.contains_key(&key) {
+            return;
+        }
+
+        if let Ok(value) = context
+            .journaled_state
+            .sload(address, slot, &mut context.db)
+        {
+            self.pre_entry(address)
+                .storage
+                .insert(key, B256::from(value.data.to_be_bytes()));
+        }
+    }
+
+    /// In diff mode, snapshots the current value of an account/slot into the `post` map.
+    fn snapshot_post<DB: Database>(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        address: Address,
+        slot: Option<U256>,
+    ) {
+        if self.mode != PrestateMode::Diff {
+            return;
+        }
+
+        if let Ok((account, _)) = context
+            .journaled_state
+            .load_account(address, &mut context.db)
+        {
+            let state = self.post_entry(address);
+            state.balance = Some(account.info.balance);
+            state.nonce = Some(account.info.nonce);
+            state.code = account.info.code.as_ref().map(|code| code.original_bytes());
+        }
+
+        if let Some(slot) = slot {
+            if let Ok(value) = context
+                .journaled_state
+                .sload(address, slot, &mut context.db)
+            {
+                let key = B256::from(slot.to_be_bytes());
+                self.post_entry(address)
+                    .storage
+                    .insert(key, B256::from(value.data.to_be_bytes()));
+            }
+        }
+    }
+}
+
+impl<DB> Inspector<DB> for PrestateTracer
+where
+    DB: Database,
+{
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        let address = interp.contract().target_address;
+        match interp.current_opcode() {
+            opcode::SLOAD => {
+                if let Ok(slot) = interp.stack().peek(0) {
+                    self.touch_storage(context, address, slot);
+                }
+            }
+            opcode::SSTORE => {
+                if let Ok(slot) = interp.stack().peek(0) {
+                    self.touch_storage(context, address, slot);
+                    // The write hasn't happened yet at `step` time; snapshot the post-state once
+                    // `step_end` confirms the opcode has actually executed.
+                    self.pending_sstore = Some((address, slot));
+                }
+            }
+            opcode::BALANCE | opcode::EXTCODESIZE | opcode::EXTCODECOPY | opcode::EXTCODEHASH => {
+                if let Ok(word) = interp.stack().peek(0) {
+                    self.touch_account(context, Address::from_word(B256::from(word.to_be_bytes())));
+                }
+            }
+            opcode::SELFBALANCE => {
+                self.touch_account(context, address);
+            }
+            _ => {}
+        }
+    }
+
+    fn step_end(&mut self, _interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if let Some((address, slot)) = self.pending_sstore.take() {
+            self.snapshot_post(context, address, Some(slot));
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<revm::interpreter::CallOutcome> {
+        self.touch_account(context, inputs.caller);
+        self.touch_account(context, inputs.target_address);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        // A value transfer changes the caller's and target's balance without ever touching
+        // storage, so snapshot both once the call (and any value transfer it performed) has
+        // actually completed. A `STATICCALL` can't change any state, so there's nothing to do.
+        if inputs.scheme != CallScheme::StaticCall {
+            self.snapshot_post(context, inputs.caller, None);
+            self.snapshot_post(context, inputs.target_address, None);
+        }
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<revm::interpreter::CreateOutcome> {
+        self.touch_account(context, inputs.caller);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.snapshot_post(context, inputs.caller, None);
+        if let Some(address) = outcome.address {
+            self.snapshot_post(context, address, None);
+        }
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        // `selfdestruct` isn't handed an `EvmContext`, so the best we can do here is make sure
+        // both accounts show up in the trace; their balance/nonce/code were already resolved by
+        // an earlier `call`/`step` if either was touched before, which is the common case.
+        let _ = value;
+        self.pre.entry(contract).or_default();
+        self.pre.entry(target).or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excludes_accounts_that_were_only_read() {
+        let pre = AccountState {
+            balance: Some(U256::from(1)),
+            ..Default::default()
+        };
+        assert!(diff_account(Some(&pre), None).is_none());
+    }
+
+    #[test]
+    fn keeps_only_fields_that_actually_changed() {
+        let pre = AccountState {
+            balance: Some(U256::from(1)),
+            nonce: Some(1),
+            ..Default::default()
+        };
+        let post = AccountState {
+            balance: Some(U256::from(2)),
+            nonce: Some(1),
+            ..Default::default()
+        };
+
+        let (pre_diff, post_diff) = diff_account(Some(&pre), Some(&post)).unwrap();
+        assert_eq!(pre_diff.balance, Some(U256::from(1)));
+        assert_eq!(post_diff.balance, Some(U256::from(2)));
+        assert!(pre_diff.nonce.is_none());
+        assert!(post_diff.nonce.is_none());
+    }
+
+    #[test]
+    fn returns_none_when_nothing_changed() {
+        let state = AccountState {
+            balance: Some(U256::from(1)),
+            ..Default::default()
+        };
+        assert!(diff_account(Some(&state), Some(&state)).is_none());
+    }
+
+    #[test]
+    fn only_includes_storage_slots_that_changed() {
+        let slot_a = B256::from(U256::from(1).to_be_bytes());
+        let slot_b = B256::from(U256::from(2).to_be_bytes());
+        let unchanged = B256::from(U256::from(9).to_be_bytes());
+
+        let mut pre = AccountState::default();
+        pre.storage.insert(slot_a, B256::ZERO);
+        pre.storage.insert(slot_b, unchanged);
+
+        let mut post = AccountState::default();
+        post.storage
+            .insert(slot_a, B256::from(U256::from(5).to_be_bytes()));
+        post.storage.insert(slot_b, unchanged);
+
+        let (pre_diff, post_diff) = diff_account(Some(&pre), Some(&post)).unwrap();
+        assert_eq!(pre_diff.storage.len(), 1);
+        assert_eq!(post_diff.storage.len(), 1);
+        assert_eq!(
+            post_diff.storage.get(&slot_a),
+            Some(&B256::from(U256::from(5).to_be_bytes()))
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use crate::call_tracer::CallTracer;
+use crate::eip3155::Eip3155Tracer;
+use crate::gas::GasInspector;
+use crate::prestate::PrestateTracer;
 use alloy_primitives::{Address, Log, B256, U256};
 use revm::{
     inspectors::CustomPrintTracer,
@@ -22,28 +26,72 @@ pub enum Hook {
 }
 
 /// An inspector that manages a stack of multiple inspectors and executes them in sequence.
-#[derive(Clone, Default)]
-pub struct InspectorStack {
+///
+/// Inspectors are invoked in a fixed, documented order so that the short-circuit semantics
+/// below behave deterministically no matter how the stack was assembled:
+///
+/// 1. the built-in, typed inspectors, in field declaration order below
+///    ([`custom_print_tracer`](Self::custom_print_tracer), then [`call_tracer`](Self::call_tracer), then
+///    [`struct_logger`](Self::struct_logger), then [`gas_inspector`](Self::gas_inspector), then
+///    [`prestate_tracer`](Self::prestate_tracer))
+/// 2. `custom_inspectors`, in the order they were pushed via
+///    [`InspectorStackConfig::with_custom_inspector`]
+///
+/// For `call`/`create`, the first inspector (in the order above) to return `Some(outcome)` wins
+/// and the rest are skipped. For `call_end`/`create_end`, the first inspector to return an
+/// outcome that differs from the one it was handed wins.
+pub struct InspectorStack<DB: Database> {
     /// An optional inspector that prints opcode traces to the console.
     pub custom_print_tracer: Option<CustomPrintTracer>,
+    /// An optional inspector that records the call graph as a [`CallTraceArena`](crate::call_tracer::CallTraceArena).
+    pub call_tracer: Option<CallTracer>,
+    /// An optional EIP-3155 struct-log tracer.
+    pub struct_logger: Option<Eip3155Tracer>,
+    /// An optional standalone gas tracker; the struct-log tracer embeds its own and does not
+    /// need this field enabled to report accurate per-opcode gas costs.
+    pub gas_inspector: Option<GasInspector>,
+    /// An optional tracer recording touched accounts and storage slots, geth `prestateTracer`-style.
+    pub prestate_tracer: Option<PrestateTracer>,
+    /// Additional inspectors layered on top of the built-in ones, driven in push order.
+    pub custom_inspectors: Vec<Box<dyn Inspector<DB>>>,
     /// The hook configuration for the inspector stack.
     pub hook: Hook,
 }
 
-impl Debug for InspectorStack {
+impl<DB: Database> Default for InspectorStack<DB> {
+    fn default() -> Self {
+        Self {
+            custom_print_tracer: None,
+            call_tracer: None,
+            struct_logger: None,
+            gas_inspector: None,
+            prestate_tracer: None,
+            custom_inspectors: Vec::new(),
+            hook: Hook::default(),
+        }
+    }
+}
+
+impl<DB: Database> Debug for InspectorStack<DB> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InspectorStack")
             .field("custom_print_tracer", &self.custom_print_tracer.is_some())
+            .field("call_tracer", &self.call_tracer.is_some())
+            .field("struct_logger", &self.struct_logger.is_some())
+            .field("gas_inspector", &self.gas_inspector.is_some())
+            .field("prestate_tracer", &self.prestate_tracer.is_some())
+            .field("custom_inspectors", &self.custom_inspectors.len())
             .field("hook", &self.hook)
             .finish()
     }
 }
 
-impl InspectorStack {
+impl<DB: Database> InspectorStack<DB> {
     /// Creates a new `InspectorStack` instance based on the provided configuration.
-    pub fn new(config: InspectorStackConfig) -> Self {
+    pub fn new(config: InspectorStackConfig<DB>) -> Self {
         let mut stack = Self {
             hook: config.hook,
+            custom_inspectors: config.custom_inspectors,
             ..Default::default()
         };
 
@@ -51,6 +99,22 @@ impl InspectorStack {
             stack.custom_print_tracer = Some(CustomPrintTracer::default());
         }
 
+        if config.use_call_tracer {
+            stack.call_tracer = Some(CallTracer::default());
+        }
+
+        if config.use_struct_logger {
+            stack.struct_logger = Some(Eip3155Tracer::default());
+        }
+
+        if config.use_gas_inspector {
+            stack.gas_inspector = Some(GasInspector::new());
+        }
+
+        if let Some(mode) = config.prestate_mode {
+            stack.prestate_tracer = Some(PrestateTracer::new(mode));
+        }
+
         stack
     }
 
@@ -63,16 +127,90 @@ impl InspectorStack {
             Hook::All => true,
         }
     }
+
+    /// Resets every stateful built-in tracer to its freshly constructed state, so the same stack
+    /// can be reused across multiple transactions (e.g. when replaying a block) without carrying
+    /// over state from one transaction to the next.
+    ///
+    /// `custom_inspectors` are left untouched, since [`Inspector`] doesn't define a reset hook;
+    /// callers relying on stateful custom inspectors across transactions must reset them directly.
+    pub fn clear(&mut self) {
+        if let Some(call_tracer) = &mut self.call_tracer {
+            call_tracer.clear();
+        }
+        if let Some(struct_logger) = &mut self.struct_logger {
+            struct_logger.clear();
+        }
+        if let Some(gas_inspector) = &mut self.gas_inspector {
+            gas_inspector.clear();
+        }
+        if let Some(prestate_tracer) = &mut self.prestate_tracer {
+            prestate_tracer.clear();
+        }
+    }
 }
 
 /// Configuration struct for the `InspectorStack`.
-#[derive(Clone, Copy, Debug, Default)]
-pub struct InspectorStackConfig {
+pub struct InspectorStackConfig<DB: Database> {
     /// Enables the opcode trace printer in the inspector.
     pub use_printer_tracer: bool,
 
+    /// Enables the geth-style call-tree tracer in the inspector.
+    pub use_call_tracer: bool,
+
+    /// Enables the EIP-3155 struct-log tracer in the inspector.
+    pub use_struct_logger: bool,
+
+    /// Enables the standalone gas tracker in the inspector.
+    pub use_gas_inspector: bool,
+
+    /// Enables the prestate/state-diff tracer in the inspector, in the given mode.
+    pub prestate_mode: Option<crate::prestate::PrestateMode>,
+
     /// Hook configuration for the inspector stack.
     pub hook: Hook,
+
+    /// Custom inspectors to be registered on the built [`InspectorStack`], in push order.
+    pub(crate) custom_inspectors: Vec<Box<dyn Inspector<DB>>>,
+}
+
+impl<DB: Database> Default for InspectorStackConfig<DB> {
+    fn default() -> Self {
+        Self {
+            use_printer_tracer: false,
+            use_call_tracer: false,
+            use_struct_logger: false,
+            use_gas_inspector: false,
+            prestate_mode: None,
+            hook: Hook::default(),
+            custom_inspectors: Vec::new(),
+        }
+    }
+}
+
+impl<DB: Database> Debug for InspectorStackConfig<DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InspectorStackConfig")
+            .field("use_printer_tracer", &self.use_printer_tracer)
+            .field("use_call_tracer", &self.use_call_tracer)
+            .field("use_struct_logger", &self.use_struct_logger)
+            .field("use_gas_inspector", &self.use_gas_inspector)
+            .field("prestate_mode", &self.prestate_mode)
+            .field("hook", &self.hook)
+            .field("custom_inspectors", &self.custom_inspectors.len())
+            .finish()
+    }
+}
+
+impl<DB: Database> InspectorStackConfig<DB> {
+    /// Registers a custom inspector to be driven alongside the built-in ones.
+    ///
+    /// Inspectors are invoked in the order they are pushed; see [`InspectorStack`] for the full
+    /// ordering and short-circuit semantics.
+    pub fn with_custom_inspector(mut self, inspector: Box<dyn Inspector<DB>>) -> Self {
+        self.custom_inspectors.push(inspector);
+        self
+    }
 }
 
 /// Macro for calling a method on multiple inspectors without dynamic dispatch.
@@ -87,32 +225,84 @@ macro_rules! call_inspectors {
     };
 }
 
-impl<DB> Inspector<DB> for InspectorStack
+impl<DB> Inspector<DB> for InspectorStack<DB>
 where
     DB: Database,
 {
     fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!(
+            [
+                &mut self.custom_print_tracer,
+                &mut self.call_tracer,
+                &mut self.struct_logger,
+                &mut self.gas_inspector,
+                &mut self.prestate_tracer
+            ],
+            |inspector| {
+                inspector.initialize_interp(interp, context);
+            }
+        );
+
+        for inspector in &mut self.custom_inspectors {
             inspector.initialize_interp(interp, context);
-        });
+        }
     }
 
     fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!(
+            [
+                &mut self.custom_print_tracer,
+                &mut self.call_tracer,
+                &mut self.struct_logger,
+                &mut self.gas_inspector,
+                &mut self.prestate_tracer
+            ],
+            |inspector| {
+                inspector.step(interp, context);
+            }
+        );
+
+        for inspector in &mut self.custom_inspectors {
             inspector.step(interp, context);
-        });
+        }
     }
 
     fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!(
+            [
+                &mut self.custom_print_tracer,
+                &mut self.call_tracer,
+                &mut self.struct_logger,
+                &mut self.gas_inspector,
+                &mut self.prestate_tracer
+            ],
+            |inspector| {
+                inspector.step_end(interp, context);
+            }
+        );
+
+        for inspector in &mut self.custom_inspectors {
             inspector.step_end(interp, context);
-        });
+        }
     }
 
     fn log(&mut self, context: &mut EvmContext<DB>, log: &Log) {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!(
+            [
+                &mut self.custom_print_tracer,
+                &mut self.call_tracer,
+                &mut self.struct_logger,
+                &mut self.gas_inspector,
+                &mut self.prestate_tracer
+            ],
+            |inspector| {
+                inspector.log(context, log);
+            }
+        );
+
+        for inspector in &mut self.custom_inspectors {
             inspector.log(context, log);
-        });
+        }
     }
 
     fn call(
@@ -120,9 +310,41 @@ where
         context: &mut EvmContext<DB>,
         inputs: &mut CallInputs,
     ) -> Option<CallOutcome> {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
-            inspector.call(context, inputs)
-        }).flatten()
+        // Each built-in is checked explicitly: the first `Some` is returned at once, and later
+        // built-ins still run while earlier ones return `None`.
+        if let Some(inspector) = &mut self.custom_print_tracer {
+            if let Some(outcome) = inspector.call(context, inputs) {
+                return Some(outcome);
+            }
+        }
+        if let Some(inspector) = &mut self.call_tracer {
+            if let Some(outcome) = inspector.call(context, inputs) {
+                return Some(outcome);
+            }
+        }
+        if let Some(inspector) = &mut self.struct_logger {
+            if let Some(outcome) = inspector.call(context, inputs) {
+                return Some(outcome);
+            }
+        }
+        if let Some(inspector) = &mut self.gas_inspector {
+            if let Some(outcome) = inspector.call(context, inputs) {
+                return Some(outcome);
+            }
+        }
+        if let Some(inspector) = &mut self.prestate_tracer {
+            if let Some(outcome) = inspector.call(context, inputs) {
+                return Some(outcome);
+            }
+        }
+
+        for inspector in &mut self.custom_inspectors {
+            if let Some(outcome) = inspector.call(context, inputs) {
+                return Some(outcome);
+            }
+        }
+
+        None
     }
 
     fn call_end(
@@ -131,7 +353,26 @@ where
         inputs: &CallInputs,
         outcome: CallOutcome,
     ) -> CallOutcome {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!(
+            [
+                &mut self.custom_print_tracer,
+                &mut self.call_tracer,
+                &mut self.struct_logger,
+                &mut self.gas_inspector,
+                &mut self.prestate_tracer
+            ],
+            |inspector| {
+                let new_ret = inspector.call_end(context, inputs, outcome.clone());
+
+                // If the inspector returns a different result or a revert with a non-empty message,
+                // we assume it wants to provide additional information.
+                if new_ret != outcome {
+                    return new_ret;
+                }
+            }
+        );
+
+        for inspector in &mut self.custom_inspectors {
             let new_ret = inspector.call_end(context, inputs, outcome.clone());
 
             // If the inspector returns a different result or a revert with a non-empty message,
@@ -139,7 +380,7 @@ where
             if new_ret != outcome {
                 return new_ret;
             }
-        });
+        }
 
         outcome
     }
@@ -149,9 +390,41 @@ where
         context: &mut EvmContext<DB>,
         inputs: &mut CreateInputs,
     ) -> Option<CreateOutcome> {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
-            inspector.create(context, inputs)
-        }).flatten()
+        // See the comment in `call` above: each built-in is checked explicitly so the first
+        // `Some` wins without skipping later built-ins when an earlier one returns `None`.
+        if let Some(inspector) = &mut self.custom_print_tracer {
+            if let Some(outcome) = inspector.create(context, inputs) {
+                return Some(outcome);
+            }
+        }
+        if let Some(inspector) = &mut self.call_tracer {
+            if let Some(outcome) = inspector.create(context, inputs) {
+                return Some(outcome);
+            }
+        }
+        if let Some(inspector) = &mut self.struct_logger {
+            if let Some(outcome) = inspector.create(context, inputs) {
+                return Some(outcome);
+            }
+        }
+        if let Some(inspector) = &mut self.gas_inspector {
+            if let Some(outcome) = inspector.create(context, inputs) {
+                return Some(outcome);
+            }
+        }
+        if let Some(inspector) = &mut self.prestate_tracer {
+            if let Some(outcome) = inspector.create(context, inputs) {
+                return Some(outcome);
+            }
+        }
+
+        for inspector in &mut self.custom_inspectors {
+            if let Some(outcome) = inspector.create(context, inputs) {
+                return Some(outcome);
+            }
+        }
+
+        None
     }
 
     fn create_end(
@@ -160,7 +433,26 @@ where
         inputs: &CreateInputs,
         outcome: CreateOutcome,
     ) -> CreateOutcome {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!(
+            [
+                &mut self.custom_print_tracer,
+                &mut self.call_tracer,
+                &mut self.struct_logger,
+                &mut self.gas_inspector,
+                &mut self.prestate_tracer
+            ],
+            |inspector| {
+                let new_ret = inspector.create_end(context, inputs, outcome.clone());
+
+                // If the inspector returns a different result or a revert with a non-empty message,
+                // we assume it wants to provide additional information.
+                if new_ret != outcome {
+                    return new_ret;
+                }
+            }
+        );
+
+        for inspector in &mut self.custom_inspectors {
             let new_ret = inspector.create_end(context, inputs, outcome.clone());
 
             // If the inspector returns a different result or a revert with a non-empty message,
@@ -168,14 +460,27 @@ where
             if new_ret != outcome {
                 return new_ret;
             }
-        });
+        }
 
         outcome
     }
 
     fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!(
+            [
+                &mut self.custom_print_tracer,
+                &mut self.call_tracer,
+                &mut self.struct_logger,
+                &mut self.gas_inspector,
+                &mut self.prestate_tracer
+            ],
+            |inspector| {
+                inspector.selfdestruct(contract, target, value);
+            }
+        );
+
+        for inspector in &mut self.custom_inspectors {
             inspector.selfdestruct(contract, target, value);
-        });
+        }
     }
 }